@@ -3,13 +3,29 @@ use std::{
     fs,
     path::{Path, PathBuf},
     process,
+    sync::atomic::{AtomicU64, Ordering},
 };
 
 use anyhow::{anyhow, bail, Context, Error, Result};
 use clap::{arg, command, Parser};
-use image::ImageError;
+#[cfg(any(feature = "raw", feature = "heif"))]
+use image::{ImageBuffer, Rgb};
+use image::{DynamicImage, ImageError};
+use rayon::prelude::*;
+use walkdir::WalkDir;
 use webp::Encoder;
 
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "bmp", "tiff", "tga", "gif", "webp"];
+
+#[cfg(feature = "raw")]
+const RAW_EXTENSIONS: &[&str] = &["nef", "cr2", "arw", "dng", "rw2", "orf"];
+
+#[cfg(feature = "heif")]
+const HEIF_EXTENSIONS: &[&str] = &["heic", "heif", "avif"];
+
+#[cfg(feature = "ffmpeg")]
+const ANIMATED_EXTENSIONS: &[&str] = &["gif", "mp4", "webm"];
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -24,6 +40,24 @@ struct Args {
 
     #[arg(short, long, default_value_t = 75f32)]
     quality: f32,
+
+    #[arg(short, long, default_value_t = num_cpus::get())]
+    threads: usize,
+
+    #[arg(short, long, default_value_t = false)]
+    recursive: bool,
+
+    #[arg(long, value_delimiter = ',')]
+    include_ext: Option<Vec<String>>,
+
+    #[arg(long, value_delimiter = ',')]
+    exclude_ext: Option<Vec<String>>,
+
+    #[arg(long, default_value_t = false)]
+    lossless: bool,
+
+    #[arg(long)]
+    max_size: Option<u64>,
 }
 
 fn main() -> Result<()> {
@@ -40,14 +74,28 @@ fn main() -> Result<()> {
         process::exit(1)
     });
 
+    let options = ConversionOptions {
+        quality: args.quality,
+        lossless: args.lossless,
+        max_size: args.max_size.map(|kb| kb * 1024),
+    };
+
     match args.directory.is_some() {
         true => {
-            if let Err(_) = process_directory(&args.directory.unwrap(), output_path, args.quality) {
+            if let Err(_) = process_directory(
+                &args.directory.unwrap(),
+                output_path,
+                options,
+                args.threads,
+                args.recursive,
+                &args.include_ext,
+                &args.exclude_ext,
+            ) {
                 bail!("Error: Failed to open directory");
             }
         }
         false => {
-            if let Err(err) = process_image(&args.input_file.unwrap(), &output_path, args.quality) {
+            if let Err(err) = process_image(&args.input_file.unwrap(), &output_path, options) {
                 bail!("Failed to process file - {:?}", err);
             }
         }
@@ -56,12 +104,28 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn process_image(input_file: &str, output_path: &Path, quality: f32) -> Result<(), Error> {
+/// Per-file conversion knobs, bundled so `process_image`/`process_directory`
+/// don't grow a positional parameter per `--flag` the CLI gains.
+#[derive(Clone, Copy)]
+struct ConversionOptions {
+    quality: f32,
+    lossless: bool,
+    max_size: Option<u64>,
+}
+
+fn process_image(
+    input_file: &str,
+    output_path: &Path,
+    options: ConversionOptions,
+) -> Result<(u64, u64), Error> {
+    let ConversionOptions {
+        quality,
+        lossless,
+        max_size,
+    } = options;
     let image_path = Path::new(input_file);
     let file_size = fs::metadata(image_path).unwrap().len();
 
-    let img = image::open(image_path)?;
-
     let file_name = image_path.file_name().unwrap_or_else(|| {
         println!("Cannot get name from file using default");
         OsStr::new("default")
@@ -69,33 +133,102 @@ fn process_image(input_file: &str, output_path: &Path, quality: f32) -> Result<(
 
     println!("Converting {:?}", file_name);
 
-    let encoder = Encoder::from_image(&img).map_err(|_| anyhow!("Failed to create encoder"))?;
-
-    let webp = encoder.encode(quality);
+    #[cfg(feature = "ffmpeg")]
+    let webp: Vec<u8> = if is_animated_file(image_path)? {
+        if lossless || max_size.is_some() {
+            eprintln!(
+                "Note: --lossless/--max-size don't apply to animated output; encoding {:?} at --quality {}",
+                file_name, quality
+            );
+        }
+        encode_animated(image_path, quality)?
+    } else {
+        let img = decode_any(image_path)?;
+        let encoder = Encoder::from_image(&img).map_err(|_| anyhow!("Failed to create encoder"))?;
+        encode_still(&encoder, quality, lossless, max_size)
+    };
+    #[cfg(not(feature = "ffmpeg"))]
+    let webp: Vec<u8> = {
+        let img = decode_any(image_path)?;
+        let encoder = Encoder::from_image(&img).map_err(|_| anyhow!("Failed to create encoder"))?;
+        encode_still(&encoder, quality, lossless, max_size)
+    };
 
     let output_path = output_path.join(file_name).with_extension("webp");
-    fs::write(&output_path, &*webp).unwrap();
+    fs::write(&output_path, &webp).unwrap();
 
     let new_file_size = fs::metadata(output_path).unwrap().len();
     let percentage_change =
         ((file_size as f64 - new_file_size as f64) / file_size as f64) * 100 as f64;
     println!(
         "Saved {:?} KB ({:?}%)",
-        (file_size - new_file_size) / 1024,
+        file_size.saturating_sub(new_file_size) / 1024,
         percentage_change as u64
     );
 
-    Ok(())
+    Ok((file_size, new_file_size))
+}
+
+/// Picks the still-image encode mode: a size budget (binary-searching quality
+/// until the output fits), lossless, or the plain lossy `quality` path.
+fn encode_still(encoder: &Encoder, quality: f32, lossless: bool, max_size: Option<u64>) -> Vec<u8> {
+    if let Some(max_bytes) = max_size {
+        let (data, chosen_quality) = encode_to_max_size(encoder, max_bytes);
+        if (data.len() as u64) <= max_bytes {
+            println!("Encoded at quality {:.1} to stay under {} KB", chosen_quality, max_bytes / 1024);
+        } else {
+            println!("Encoded at quality {:.1}; output is still over {} KB", chosen_quality, max_bytes / 1024);
+        }
+        data
+    } else if lossless {
+        encoder.encode_lossless().to_vec()
+    } else {
+        encoder.encode(quality).to_vec()
+    }
 }
 
-fn get_files_in_dir(dir: &str) -> Result<Vec<PathBuf>> {
+/// Binary-searches the lossy quality parameter for the highest value whose
+/// encoded output still fits under `max_bytes`. If even quality 1.0 does not
+/// fit, the quality-1.0 output is returned and is larger than `max_bytes`.
+fn encode_to_max_size(encoder: &Encoder, max_bytes: u64) -> (Vec<u8>, f32) {
+    let mut low = 1.0f32;
+    let mut high = 100.0f32;
+    let mut best = encoder.encode(low).to_vec();
+    let mut best_quality = low;
+
+    for _ in 0..7 {
+        let mid = (low + high) / 2.0;
+        let candidate = encoder.encode(mid);
+        if (candidate.len() as u64) <= max_bytes {
+            best = candidate.to_vec();
+            best_quality = mid;
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    (best, best_quality)
+}
+
+fn get_files_in_dir(
+    dir: &str,
+    recursive: bool,
+    include_ext: &Option<Vec<String>>,
+    exclude_ext: &Option<Vec<String>>,
+) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
-    for entry in fs::read_dir(dir)? {
+    let mut walker = WalkDir::new(dir).min_depth(1);
+    if !recursive {
+        walker = walker.max_depth(1);
+    }
+
+    for entry in walker {
         let entry = entry?;
-        let path = entry.path();
+        let path = entry.path().to_path_buf();
 
-        if path.is_file() && is_image_file(&path) {
+        if path.is_file() && is_image_file(&path, include_ext, exclude_ext) {
             files.push(path);
         }
     }
@@ -103,24 +236,336 @@ fn get_files_in_dir(dir: &str) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
-fn is_image_file(file: &Path) -> bool {
-    image::open(file).is_ok()
+/// Cheap extension-based filter - only files that pass this get handed to
+/// `decode_any`, so directory scans no longer decode every candidate twice.
+fn is_image_file(
+    file: &Path,
+    include_ext: &Option<Vec<String>>,
+    exclude_ext: &Option<Vec<String>>,
+) -> bool {
+    let Some(ext) = file.extension().and_then(OsStr::to_str) else {
+        return false;
+    };
+    let ext = ext.to_lowercase();
+
+    if let Some(exclude) = exclude_ext {
+        if exclude.iter().any(|e| e.eq_ignore_ascii_case(&ext)) {
+            return false;
+        }
+    }
+
+    if let Some(include) = include_ext {
+        return include.iter().any(|e| e.eq_ignore_ascii_case(&ext));
+    }
+
+    #[cfg_attr(not(any(feature = "raw", feature = "heif", feature = "ffmpeg")), allow(unused_mut))]
+    let mut allowed = IMAGE_EXTENSIONS.to_vec();
+    #[cfg(feature = "raw")]
+    allowed.extend_from_slice(RAW_EXTENSIONS);
+    #[cfg(feature = "heif")]
+    allowed.extend_from_slice(HEIF_EXTENSIONS);
+    #[cfg(feature = "ffmpeg")]
+    allowed.extend_from_slice(ANIMATED_EXTENSIONS);
+
+    allowed.contains(&ext.as_str())
 }
 
-fn process_directory(dir: &str, output_path: &Path, quality: f32) -> Result<()> {
-    let files = get_files_in_dir(&dir)?;
+/// Decodes any supported input - standard formats via `image`, plus RAW and
+/// HEIF/AVIF when their respective features are enabled - into a single
+/// `DynamicImage` that the WebP encode tail doesn't need to know about.
+fn decode_any(path: &Path) -> Result<DynamicImage> {
+    #[cfg(feature = "raw")]
+    if is_raw_file(path) {
+        return decode_raw(path);
+    }
 
-    for file in files {
-        if let Err(_) = process_image(file.to_str().unwrap(), output_path, quality) {
-            eprintln!(
-                "Error processing file: {:?} - Skipping...",
-                file.file_name().unwrap()
-            );
+    #[cfg(feature = "heif")]
+    if is_heif_file(path) {
+        return decode_heif(path);
+    }
+
+    Ok(image::open(path)?)
+}
+
+#[cfg(feature = "raw")]
+fn is_raw_file(file: &Path) -> bool {
+    file.extension()
+        .and_then(OsStr::to_str)
+        .map(|ext| RAW_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+#[cfg(feature = "raw")]
+fn decode_raw(path: &Path) -> Result<DynamicImage> {
+    let raw_image = rawloader::decode_file(path).context("Failed to decode RAW file")?;
+    let mut pipeline = imagepipe::Pipeline::new_from_source(imagepipe::ImageSource::Raw(raw_image))
+        .map_err(|err| anyhow!("Failed to build RAW pipeline: {:?}", err))?;
+    let decoded = pipeline
+        .output_8bit(None)
+        .map_err(|err| anyhow!("Failed to render RAW pipeline: {:?}", err))?;
+
+    let buffer = ImageBuffer::<Rgb<u8>, _>::from_raw(
+        decoded.width as u32,
+        decoded.height as u32,
+        decoded.data,
+    )
+    .ok_or_else(|| anyhow!("RAW output buffer did not match the reported dimensions"))?;
+
+    Ok(DynamicImage::ImageRgb8(buffer))
+}
+
+#[cfg(feature = "heif")]
+fn is_heif_file(file: &Path) -> bool {
+    file.extension()
+        .and_then(OsStr::to_str)
+        .map(|ext| HEIF_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+#[cfg(feature = "heif")]
+fn decode_heif(path: &Path) -> Result<DynamicImage> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let lib_heif = LibHeif::new();
+    let ctx = HeifContext::read_from_file(
+        path.to_str().ok_or_else(|| anyhow!("HEIF path is not valid UTF-8"))?,
+    )
+    .map_err(|err| anyhow!("Failed to read HEIF file: {:?}", err))?;
+    let handle = ctx
+        .primary_image_handle()
+        .map_err(|err| anyhow!("Failed to get primary HEIF image: {:?}", err))?;
+    let image = lib_heif
+        .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgb), None)
+        .map_err(|err| anyhow!("Failed to decode HEIF image: {:?}", err))?;
+
+    let plane = image
+        .planes()
+        .interleaved
+        .ok_or_else(|| anyhow!("HEIF image has no interleaved RGB plane"))?;
+
+    let width = plane.width as usize;
+    let height = plane.height as usize;
+    let stride = plane.stride;
+    let data = plane.data;
+    let row_bytes = width * 3;
+
+    if stride < row_bytes {
+        bail!(
+            "HEIF plane stride ({}) is smaller than the row width ({} bytes)",
+            stride,
+            row_bytes
+        );
+    }
+    if data.len() < stride * height {
+        bail!(
+            "HEIF plane data ({} bytes) is too short for its stride and height ({} bytes)",
+            data.len(),
+            stride * height
+        );
+    }
+
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for row in 0..height {
+        let start = row * stride;
+        pixels.extend_from_slice(&data[start..start + row_bytes]);
+    }
+
+    let buffer = ImageBuffer::<Rgb<u8>, _>::from_raw(width as u32, height as u32, pixels)
+        .ok_or_else(|| anyhow!("HEIF output buffer did not match the reported dimensions"))?;
+
+    Ok(DynamicImage::ImageRgb8(buffer))
+}
+
+#[cfg(feature = "ffmpeg")]
+fn is_animated_file(path: &Path) -> Result<bool> {
+    match path
+        .extension()
+        .and_then(OsStr::to_str)
+        .map(|ext| ext.to_lowercase())
+        .as_deref()
+    {
+        Some("mp4") | Some("webm") => Ok(true),
+        Some("gif") => {
+            ffmpeg_next::init().context("Failed to initialize ffmpeg")?;
+            let input = ffmpeg_next::format::input(&path).context("Failed to open GIF")?;
+            let stream = input
+                .streams()
+                .best(ffmpeg_next::media::Type::Video)
+                .ok_or_else(|| anyhow!("GIF has no video stream"))?;
+            Ok(stream.frames() > 1)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// One decoded, scaled RGBA frame. Owns its pixel buffer so it outlives the
+/// ffmpeg frame it was copied out of - `AnimFrame`/`AnimEncoder` only borrow,
+/// they don't copy, so the buffers must stay alive until `try_encode()` runs.
+#[cfg(feature = "ffmpeg")]
+struct DecodedFrame {
+    rgba: Vec<u8>,
+    timestamp_ms: i32,
+}
+
+#[cfg(feature = "ffmpeg")]
+fn drain_decoded_frames(
+    decoder: &mut ffmpeg_next::decoder::Video,
+    scaler: &mut ffmpeg_next::software::scaling::Context,
+    time_base: ffmpeg_next::Rational,
+    frames: &mut Vec<DecodedFrame>,
+) -> Result<()> {
+    let mut decoded = ffmpeg_next::util::frame::Video::empty();
+    while decoder.receive_frame(&mut decoded).is_ok() {
+        let mut rgba_frame = ffmpeg_next::util::frame::Video::empty();
+        scaler.run(&decoded, &mut rgba_frame)?;
+
+        let width = rgba_frame.width() as usize;
+        let height = rgba_frame.height() as usize;
+        let stride = rgba_frame.stride(0);
+        let data = rgba_frame.data(0);
+        let row_bytes = width * 4;
+
+        let mut rgba = Vec::with_capacity(row_bytes * height);
+        for row in 0..height {
+            let start = row * stride;
+            rgba.extend_from_slice(&data[start..start + row_bytes]);
         }
+
+        let timestamp_ms =
+            (decoded.timestamp().unwrap_or(0) as f64 * f64::from(time_base) * 1000.0) as i32;
+        frames.push(DecodedFrame { rgba, timestamp_ms });
     }
 
     Ok(())
 }
+
+/// Demuxes/decodes an animated input (multi-frame GIF, or a short clip) with
+/// `ffmpeg-next` and re-assembles the frames into an animated WebP at the
+/// given quality, reusing the timestamps ffmpeg reports for frame durations.
+#[cfg(feature = "ffmpeg")]
+fn encode_animated(path: &Path, quality: f32) -> Result<Vec<u8>> {
+    ffmpeg_next::init().context("Failed to initialize ffmpeg")?;
+
+    let mut input = ffmpeg_next::format::input(&path).context("Failed to open input")?;
+    let stream = input
+        .streams()
+        .best(ffmpeg_next::media::Type::Video)
+        .ok_or_else(|| anyhow!("No video stream found"))?;
+    let stream_index = stream.index();
+    let time_base = stream.time_base();
+
+    let mut decoder = ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .video()?;
+    let width = decoder.width();
+    let height = decoder.height();
+
+    let mut scaler = ffmpeg_next::software::scaling::Context::get(
+        decoder.format(),
+        width,
+        height,
+        ffmpeg_next::format::Pixel::RGBA,
+        width,
+        height,
+        ffmpeg_next::software::scaling::Flags::BILINEAR,
+    )?;
+
+    let mut frames = Vec::new();
+    for (packet_stream, packet) in input.packets() {
+        if packet_stream.index() == stream_index {
+            decoder.send_packet(&packet)?;
+            drain_decoded_frames(&mut decoder, &mut scaler, time_base, &mut frames)?;
+        }
+    }
+    decoder.send_eof()?;
+    drain_decoded_frames(&mut decoder, &mut scaler, time_base, &mut frames)?;
+
+    let mut config = webp::WebPConfig::new().map_err(|_| anyhow!("Failed to create WebP config"))?;
+    config.quality = quality;
+    let mut anim_encoder = webp::AnimEncoder::new(width, height, &config);
+    for frame in &frames {
+        anim_encoder.add_frame(webp::AnimFrame::from_rgba(
+            &frame.rgba,
+            width,
+            height,
+            frame.timestamp_ms,
+        ));
+    }
+
+    let webp_data = anim_encoder
+        .try_encode()
+        .map_err(|err| anyhow!("Failed to encode animated WebP: {:?}", err))?;
+
+    Ok(webp_data.to_vec())
+}
+
+fn process_directory(
+    dir: &str,
+    output_path: &Path,
+    options: ConversionOptions,
+    threads: usize,
+    recursive: bool,
+    include_ext: &Option<Vec<String>>,
+    exclude_ext: &Option<Vec<String>>,
+) -> Result<()> {
+    let input_root = Path::new(dir);
+    let files = get_files_in_dir(dir, recursive, include_ext, exclude_ext)?;
+
+    let _ = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global();
+
+    let total_original = AtomicU64::new(0);
+    let total_saved = AtomicU64::new(0);
+
+    files.par_iter().for_each(|file| {
+        let file_output_dir = match file.strip_prefix(input_root).ok().and_then(Path::parent) {
+            Some(parent) if !parent.as_os_str().is_empty() => {
+                let nested = output_path.join(parent);
+                if let Err(err) = fs::create_dir_all(&nested) {
+                    eprintln!(
+                        "Failed to create output directory {:?}: {} - Skipping {:?}...",
+                        nested,
+                        err,
+                        file.file_name().unwrap()
+                    );
+                    return;
+                }
+                nested
+            }
+            _ => output_path.to_path_buf(),
+        };
+
+        match process_image(file.to_str().unwrap(), &file_output_dir, options) {
+            Ok((original_size, new_size)) => {
+                total_original.fetch_add(original_size, Ordering::Relaxed);
+                total_saved.fetch_add(original_size.saturating_sub(new_size), Ordering::Relaxed);
+            }
+            Err(_) => {
+                eprintln!(
+                    "Error processing file: {:?} - Skipping...",
+                    file.file_name().unwrap()
+                );
+            }
+        }
+    });
+
+    let total_original = total_original.load(Ordering::Relaxed);
+    let total_saved = total_saved.load(Ordering::Relaxed);
+    let average_percentage = if total_original > 0 {
+        (total_saved as f64 / total_original as f64) * 100.0
+    } else {
+        0.0
+    };
+
+    println!(
+        "Total saved {} KB (average {:.0}%)",
+        total_saved / 1024,
+        average_percentage
+    );
+
+    Ok(())
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,9 +574,13 @@ mod tests {
     fn test_process_image() {
         let input_file = "./test_assets/test_img.jpg";
         let output_path = Path::new("./assets");
-        let quality = 70.0;
+        let options = ConversionOptions {
+            quality: 70.0,
+            lossless: false,
+            max_size: None,
+        };
 
-        let result = process_image(input_file, output_path, quality);
+        let result = process_image(input_file, output_path, options);
 
         assert!(result.is_ok())
     }
@@ -140,10 +589,78 @@ mod tests {
     fn test_process_directory() {
         let directory = "test_assets";
         let output_path = Path::new("./assets");
-        let quality = 70.0;
+        let options = ConversionOptions {
+            quality: 70.0,
+            lossless: false,
+            max_size: None,
+        };
 
-        let result = process_directory(directory, output_path, quality);
+        let result = process_directory(directory, output_path, options, 1, false, &None, &None);
 
         assert!(result.is_ok())
     }
+
+    #[test]
+    fn test_is_image_file_default_extensions() {
+        assert!(is_image_file(Path::new("photo.jpg"), &None, &None));
+        assert!(is_image_file(Path::new("PHOTO.JPG"), &None, &None));
+        assert!(!is_image_file(Path::new("document.pdf"), &None, &None));
+        assert!(!is_image_file(Path::new("no_extension"), &None, &None));
+    }
+
+    #[test]
+    fn test_is_image_file_include_ext_overrides_default_list() {
+        let include = Some(vec!["png".to_string()]);
+        assert!(is_image_file(Path::new("photo.png"), &include, &None));
+        assert!(!is_image_file(Path::new("photo.jpg"), &include, &None));
+    }
+
+    #[test]
+    fn test_is_image_file_exclude_ext_takes_precedence_over_include() {
+        let include = Some(vec!["png".to_string()]);
+        let exclude = Some(vec!["png".to_string()]);
+        assert!(!is_image_file(Path::new("photo.png"), &include, &exclude));
+    }
+
+    #[test]
+    fn test_is_image_file_exclude_ext_filters_default_list() {
+        let exclude = Some(vec!["jpg".to_string()]);
+        assert!(!is_image_file(Path::new("photo.jpg"), &None, &exclude));
+        assert!(is_image_file(Path::new("photo.png"), &None, &exclude));
+    }
+
+    /// Deterministic noisy RGB buffer - noise keeps lossy output size
+    /// responsive to quality, unlike a flat color which webp squashes to
+    /// near-nothing at any quality.
+    fn noisy_rgb_image(width: u32, height: u32) -> Vec<u8> {
+        (0..(width * height * 3))
+            .map(|i| ((i as u32).wrapping_mul(2654435761) >> 24) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_to_max_size_stays_under_budget() {
+        let width = 64;
+        let height = 64;
+        let image = noisy_rgb_image(width, height);
+        let encoder = webp::Encoder::from_rgb(&image, width, height);
+
+        let max_bytes = 2_000;
+        let (data, chosen_quality) = encode_to_max_size(&encoder, max_bytes);
+
+        assert!(data.len() as u64 <= max_bytes);
+        assert!((1.0..=100.0).contains(&chosen_quality));
+    }
+
+    #[test]
+    fn test_encode_to_max_size_converges_toward_high_quality_when_unconstrained() {
+        let width = 64;
+        let height = 64;
+        let image = noisy_rgb_image(width, height);
+        let encoder = webp::Encoder::from_rgb(&image, width, height);
+
+        let (_, chosen_quality) = encode_to_max_size(&encoder, 10_000_000);
+
+        assert!(chosen_quality > 90.0);
+    }
 }